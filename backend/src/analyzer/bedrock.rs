@@ -0,0 +1,144 @@
+use anyhow::Result;
+use aws_config::Region;
+use aws_sdk_bedrockruntime::primitives::Blob;
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::json;
+
+use crate::{resize_image, usage::Usage};
+
+use super::{Analysis, ReceiptAnalyzer};
+
+pub(crate) const MODEL_ID: &str = "anthropic.claude-3-5-sonnet-20240620-v1:0";
+
+/// Analyzes receipts with Claude 3.5 Sonnet via Amazon Bedrock.
+pub struct BedrockClaudeAnalyzer {
+    client: aws_sdk_bedrockruntime::Client,
+}
+
+impl BedrockClaudeAnalyzer {
+    pub async fn new() -> Self {
+        let config = aws_config::load_defaults(aws_config::BehaviorVersion::v2024_03_28())
+            .await
+            .into_builder()
+            .region(Region::new("us-east-1"))
+            .build();
+
+        Self {
+            client: aws_sdk_bedrockruntime::Client::new(&config),
+        }
+    }
+
+    pub fn from_client(client: aws_sdk_bedrockruntime::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ReceiptAnalyzer for BedrockClaudeAnalyzer {
+    async fn analyze(&self, image: &[u8]) -> Result<Analysis> {
+        let image = resize_image(image, 1024 * 1024).await?;
+        let base64_image = general_purpose::STANDARD.encode(image);
+
+        let example_format = json!({
+            "brand": "{店の名前}",
+            "store": "{支店名}",
+            "date": "{日付 YYYY-MM-DD}",
+            "items": [
+                {
+                    "name": "{商品名1}",
+                    "price": "{価格1}"
+                },
+                {
+                    "name": "{商品名2}",
+                    "price": "{価格2}"
+                },
+                {
+                    "name": "外税",
+                    "price": "{外税の項目があれば、アイテムの総和が税込み合計金額と一致するように調整用の外税の合計金額を入れる。なければ外税の項目自体を削除してよい}"
+                }
+            ],
+            "total": "{税込み合計金額、アイテムから計算せず、レシートに書いてあるとおりを入れる}",
+            "confidence": "{どれくらい正確に読み取れたと判断できるか、0.0 から 1.0 の間の数値を入れる}"
+        });
+
+        let bedrock_request = json!({
+            "anthropic_version": "bedrock-2023-05-31",
+            "max_tokens": 1000,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": [
+                        {
+                            "type": "image",
+                            "source": {
+                                "type": "base64",
+                                "media_type": "image/jpeg",
+                                "data": base64_image
+                            }
+                        },
+                        {
+                            "type": "text",
+                            "text": format!(
+                                concat!(
+                                    "画像のレシートを解析し、店名、日付、購入品目と金額、合計金額を抽出してください。\n",
+                                    "\n",
+                                    "# 注意\n",
+                                    "\n",
+                                    "- レシートではなくクレジットカード売上票や請求書の場合もあります。この場合は内訳は書いていないので、「不明」というアイテムが一つのレシートとみなしてください。\n",
+                                    "- 円記号を目印にして認識してください。\n",
+                                    "- 商品は1つしかない場合もあれば複数個ある場合もあります。\n",
+                                    "- レシートに書いてある合計金額を最も優先し、計算が合わない場合は商品の項目で調整してください。\n",
+                                    "- 不明な箇所には `不明` と出力してください。\n",
+                                    "\n",
+                                    "# 出力フォーマット\n",
+                                    "\n",
+                                    "- 以下の形式の JSON フォーマットを返してください。\n",
+                                    "- 説明は一切不要なので結果のみを返してください。\n",
+                                    "- Markdown のマーカーブロックでは囲まないでください。\n",
+                                    "\n",
+                                    "```json\n",
+                                    "{example_format}\n",
+                                    "```\n",
+                                ),
+                                example_format=example_format
+                            ),
+                        }
+                    ]
+                }
+            ]
+        });
+
+        let response = self
+            .client
+            .invoke_model()
+            .model_id(MODEL_ID)
+            .content_type("application/json")
+            .accept("application/json")
+            .body(Blob::new(serde_json::to_string(&bedrock_request)?))
+            .send()
+            .await?;
+
+        let response_body: serde_json::Value = serde_json::from_slice(response.body().as_ref())?;
+        let analysis_result = response_body["content"][0]["text"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Failed to extract analysis result"))?;
+
+        let usage = Usage {
+            input_tokens: response_body["usage"]["input_tokens"]
+                .as_u64()
+                .unwrap_or_default(),
+            output_tokens: response_body["usage"]["output_tokens"]
+                .as_u64()
+                .unwrap_or_default(),
+        };
+
+        let receipt: crate::Receipt = serde_json::from_str(analysis_result)?;
+
+        Ok(Analysis {
+            receipt: receipt.with_parsed_money(),
+            usage,
+            model_id: MODEL_ID.to_string(),
+        })
+    }
+}
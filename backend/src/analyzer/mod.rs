@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use crate::{usage::Usage, Receipt};
+
+mod bedrock;
+mod openai;
+
+pub use bedrock::{BedrockClaudeAnalyzer, MODEL_ID as BEDROCK_MODEL_ID};
+pub use openai::{OpenAiVisionAnalyzer, DEFAULT_MODEL as OPENAI_DEFAULT_MODEL};
+
+/// The result of a single `analyze` call: the extracted receipt plus the
+/// token usage and model id it cost, so callers can account for spend.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub receipt: Receipt,
+    pub usage: Usage,
+    pub model_id: String,
+}
+
+/// Backend-agnostic interface for turning a receipt image into structured data.
+///
+/// Implementations are free to call out to whatever vision model/service they
+/// like; the only contract is "bytes in, `Analysis` out".
+#[async_trait]
+pub trait ReceiptAnalyzer: Send + Sync {
+    async fn analyze(&self, image: &[u8]) -> Result<Analysis>;
+}
+
+/// Builds the analyzer backend selected by the `ANALYZER_BACKEND` environment
+/// variable. Defaults to `bedrock` when unset.
+pub async fn create_analyzer_from_env() -> Result<Arc<dyn ReceiptAnalyzer>> {
+    let backend = std::env::var("ANALYZER_BACKEND").unwrap_or_else(|_| "bedrock".to_string());
+
+    match backend.as_str() {
+        "bedrock" => Ok(Arc::new(BedrockClaudeAnalyzer::new().await)),
+        "openai" => Ok(Arc::new(OpenAiVisionAnalyzer::from_env()?)),
+        other => Err(anyhow!(
+            "unknown ANALYZER_BACKEND: {other} (expected `bedrock` or `openai`)"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_unknown_backend() {
+        std::env::set_var("ANALYZER_BACKEND", "not-a-real-backend");
+        let result = create_analyzer_from_env().await;
+        std::env::remove_var("ANALYZER_BACKEND");
+
+        let err = result.err().expect("unknown backend should be rejected");
+        assert!(err.to_string().contains("unknown ANALYZER_BACKEND"));
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::json;
+
+use crate::{resize_image, usage::Usage};
+
+use super::{Analysis, ReceiptAnalyzer};
+
+pub(crate) const DEFAULT_MODEL: &str = "gpt-4o";
+
+/// Analyzes receipts with an OpenAI vision model over the Chat Completions API.
+pub struct OpenAiVisionAnalyzer {
+    client: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiVisionAnalyzer {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            model: DEFAULT_MODEL.to_string(),
+        }
+    }
+
+    /// Reads `OPENAI_API_KEY` (required) and `OPENAI_MODEL` (optional) from
+    /// the environment.
+    pub fn from_env() -> Result<Self> {
+        let api_key =
+            std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY is not set")?;
+        let mut analyzer = Self::new(api_key);
+        if let Ok(model) = std::env::var("OPENAI_MODEL") {
+            analyzer.model = model;
+        }
+        Ok(analyzer)
+    }
+}
+
+#[async_trait]
+impl ReceiptAnalyzer for OpenAiVisionAnalyzer {
+    async fn analyze(&self, image: &[u8]) -> Result<Analysis> {
+        let image = resize_image(image, 1024 * 1024).await?;
+        let base64_image = general_purpose::STANDARD.encode(image);
+
+        let prompt = concat!(
+            "画像のレシートを解析し、店名(brand)、支店名(store)、日付(date, YYYY-MM-DD)、",
+            "購入品目(items: name, price)、合計金額(total)、読み取り精度(confidence: 0.0〜1.0)を",
+            "抽出し、次のフィールドを持つ JSON オブジェクトのみを返してください: ",
+            "brand, store, date, items (name, price の配列), total, confidence。",
+            "説明や Markdown のコードブロックは付けないでください。"
+        );
+
+        let request_body = json!({
+            "model": self.model,
+            "response_format": { "type": "json_object" },
+            "messages": [
+                {
+                    "role": "user",
+                    "content": [
+                        { "type": "text", "text": prompt },
+                        {
+                            "type": "image_url",
+                            "image_url": {
+                                "url": format!("data:image/jpeg;base64,{base64_image}")
+                            }
+                        }
+                    ]
+                }
+            ]
+        });
+
+        let response = self
+            .client
+            .post("https://api.openai.com/v1/chat/completions")
+            .bearer_auth(&self.api_key)
+            .json(&request_body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let response_body: serde_json::Value = response.json().await?;
+        let analysis_result = response_body["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Failed to extract analysis result"))?;
+
+        let usage = Usage {
+            input_tokens: response_body["usage"]["prompt_tokens"]
+                .as_u64()
+                .unwrap_or_default(),
+            output_tokens: response_body["usage"]["completion_tokens"]
+                .as_u64()
+                .unwrap_or_default(),
+        };
+
+        let receipt: crate::Receipt = serde_json::from_str(analysis_result)?;
+
+        Ok(Analysis {
+            receipt: receipt.with_parsed_money(),
+            usage,
+            model_id: self.model.clone(),
+        })
+    }
+}
@@ -1,16 +1,39 @@
 use anyhow::Result;
-use aws_config::BehaviorVersion;
-use axum::{extract::State, routing::post, Json, Router};
+use async_trait::async_trait;
+use axum::{
+    extract::{DefaultBodyLimit, FromRequest, Multipart, Request, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
 use base64::{engine::general_purpose, Engine as _};
 use lambda_http::{run, Error};
-use receipt_analyzer::{create_bedrock_client, Receipt};
+use receipt_analyzer::{
+    analyzer::ReceiptAnalyzer,
+    money::{Currency, Money, StaticRateTable},
+    slack::SlackClient,
+    usage::{CostEstimate, UsageAggregator, UsageTotals},
+    Receipt,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Arc;
+use std::{env, sync::Arc};
+
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// Optional target-currency conversion for `total_money`, configured via
+/// `FX_TARGET_CURRENCY` and `FX_RATE_<FROM>_<TO>` environment variables.
+struct FxConfig {
+    target: Currency,
+    rates: StaticRateTable,
+}
 
 #[derive(Clone)]
 struct AppState {
-    bedrock_client: Arc<aws_sdk_bedrockruntime::Client>,
+    analyzer: Arc<dyn ReceiptAnalyzer>,
+    usage_aggregator: Arc<UsageAggregator>,
+    slack: Option<Arc<SlackClient>>,
+    fx: Option<Arc<FxConfig>>,
 }
 
 #[derive(Deserialize)]
@@ -18,20 +41,221 @@ struct ReceiptRequest {
     image: String,
 }
 
+#[derive(Serialize)]
+struct ReceiptAnalysis {
+    receipt: Receipt,
+    usage: receipt_analyzer::usage::Usage,
+    cost_estimate: Option<CostEstimate>,
+    slack_post_error: Option<String>,
+    converted_total: Option<Money>,
+}
+
 #[derive(Serialize)]
 struct AnalysisResponse {
-    result: Receipt,
+    results: Vec<ReceiptAnalysis>,
+}
+
+/// Input to `/analyze`: either a single base64-encoded image in a JSON body,
+/// or one or more `image` file parts in a `multipart/form-data` body.
+enum AnalyzeInput {
+    Json(ReceiptRequest),
+    Multipart(Vec<Vec<u8>>),
+}
+
+#[async_trait]
+impl<S> FromRequest<S> for AnalyzeInput
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let is_multipart = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with("multipart/form-data"));
+
+        if is_multipart {
+            let mut multipart = Multipart::from_request(req, state)
+                .await
+                .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+
+            let mut images = Vec::new();
+            while let Some(field) = multipart
+                .next_field()
+                .await
+                .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?
+            {
+                if field.name() != Some("image") {
+                    continue;
+                }
+
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+                images.push(bytes.to_vec());
+            }
+
+            if images.is_empty() {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "expected at least one `image` part".to_string(),
+                ));
+            }
+
+            Ok(AnalyzeInput::Multipart(images))
+        } else {
+            let Json(payload) = Json::<ReceiptRequest>::from_request(req, state)
+                .await
+                .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+            Ok(AnalyzeInput::Json(payload))
+        }
+    }
 }
 
 #[axum::debug_handler]
 async fn analyze_receipt(
     State(state): State<AppState>,
-    Json(payload): Json<ReceiptRequest>,
-) -> Result<Json<Value>, axum::http::StatusCode> {
-    let image_data = general_purpose::STANDARD.decode(&payload.image).unwrap();
-    match receipt_analyzer::analyze(&state.bedrock_client, &image_data).await {
-        Ok(result) => Ok(Json(serde_json::json!(AnalysisResponse { result }))),
-        Err(_) => Err(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+    input: AnalyzeInput,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let images = match input {
+        AnalyzeInput::Json(payload) => {
+            let image = general_purpose::STANDARD
+                .decode(&payload.image)
+                .map_err(|err| (StatusCode::BAD_REQUEST, format!("invalid base64 image: {err}")))?;
+            vec![image]
+        }
+        AnalyzeInput::Multipart(images) => images,
+    };
+
+    let mut results = Vec::with_capacity(images.len());
+    for image in &images {
+        let analysis = state.analyzer.analyze(image).await.map_err(|err| {
+            tracing::error!("analyze failed: {err:#}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to analyze receipt".to_string(),
+            )
+        })?;
+
+        let cost_estimate = analysis.usage.estimate_cost(&analysis.model_id);
+        state.usage_aggregator.record(analysis.usage, cost_estimate);
+
+        let slack_post_error = if let Some(slack) = &state.slack {
+            slack
+                .post_receipt_with_image(&analysis.receipt, Some(image))
+                .await
+                .err()
+                .map(|err| err.to_string())
+        } else {
+            None
+        };
+
+        let converted_total = state.fx.as_ref().and_then(|fx| {
+            analysis
+                .receipt
+                .total_money
+                .and_then(|total| total.convert(fx.target, &fx.rates))
+        });
+
+        results.push(ReceiptAnalysis {
+            receipt: analysis.receipt,
+            usage: analysis.usage,
+            cost_estimate,
+            slack_post_error,
+            converted_total,
+        });
+    }
+
+    Ok(Json(serde_json::json!(AnalysisResponse { results })))
+}
+
+async fn usage(State(state): State<AppState>) -> Json<UsageTotals> {
+    Json(state.usage_aggregator.snapshot())
+}
+
+fn max_upload_bytes() -> usize {
+    env::var("MAX_UPLOAD_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request as HttpRequest;
+
+    #[tokio::test]
+    async fn parses_json_body() {
+        let body = serde_json::json!({ "image": "aGVsbG8=" }).to_string();
+        let req = HttpRequest::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        let input = AnalyzeInput::from_request(req, &())
+            .await
+            .expect("JSON body should parse");
+
+        match input {
+            AnalyzeInput::Json(payload) => assert_eq!(payload.image, "aGVsbG8="),
+            AnalyzeInput::Multipart(_) => panic!("expected AnalyzeInput::Json"),
+        }
+    }
+
+    #[tokio::test]
+    async fn parses_multipart_body() {
+        let boundary = "X-TEST-BOUNDARY";
+        let body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"image\"; filename=\"receipt.jpg\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n\
+             hello\r\n\
+             --{boundary}--\r\n"
+        );
+        let req = HttpRequest::builder()
+            .header(
+                CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+
+        let input = AnalyzeInput::from_request(req, &())
+            .await
+            .expect("multipart body should parse");
+
+        match input {
+            AnalyzeInput::Multipart(images) => {
+                assert_eq!(images, vec![b"hello".to_vec()]);
+            }
+            AnalyzeInput::Json(_) => panic!("expected AnalyzeInput::Multipart"),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_multipart_body_with_no_image_part() {
+        let boundary = "X-TEST-BOUNDARY";
+        let body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"not_image\"\r\n\r\n\
+             hello\r\n\
+             --{boundary}--\r\n"
+        );
+        let req = HttpRequest::builder()
+            .header(
+                CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+
+        let result = AnalyzeInput::from_request(req, &()).await;
+        assert!(result.is_err());
     }
 }
 
@@ -43,12 +267,36 @@ async fn main() -> Result<(), Error> {
         .without_time()
         .init();
 
-    let bedrock_client = Arc::new(create_bedrock_client().await);
+    let analyzer = receipt_analyzer::analyzer::create_analyzer_from_env().await?;
+    let slack = match SlackClient::from_env() {
+        Ok(client) => Some(Arc::new(client)),
+        Err(_) => {
+            tracing::info!("SLACK_WEBHOOK_URL is not set; Slack delivery is disabled");
+            None
+        }
+    };
+
+    let fx = env::var("FX_TARGET_CURRENCY")
+        .ok()
+        .and_then(|code| Currency::parse_code(&code))
+        .map(|target| {
+            Arc::new(FxConfig {
+                target,
+                rates: StaticRateTable::from_env(),
+            })
+        });
 
-    let app_state = AppState { bedrock_client };
+    let app_state = AppState {
+        analyzer,
+        usage_aggregator: Arc::new(UsageAggregator::new()),
+        slack,
+        fx,
+    };
 
     let app = Router::new()
         .route("/analyze", post(analyze_receipt))
+        .layer(DefaultBodyLimit::max(max_upload_bytes()))
+        .route("/usage", get(usage))
         .with_state(app_state);
 
     run(app).await
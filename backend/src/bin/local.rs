@@ -1,14 +1,37 @@
 use anyhow::Result;
+use receipt_analyzer::slack::SlackClient;
 use std::{env, fs};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let bedrock_client = receipt_analyzer::create_bedrock_client().await;
+    let analyzer = receipt_analyzer::analyzer::create_analyzer_from_env().await?;
+
+    let mut image_path = None;
+    let mut post_to_slack = false;
+    for arg in env::args().skip(1) {
+        if arg == "--post-to-slack" {
+            post_to_slack = true;
+        } else {
+            image_path = Some(arg);
+        }
+    }
+    let image_path = image_path.expect("missing image argument");
 
-    let image_path = env::args().nth(1).expect("missing image argument");
     let image = fs::read(image_path)?;
-    let result = receipt_analyzer::analyze(&bedrock_client, &image).await?;
-    println!("{result:#?}");
+    let analysis = analyzer.analyze(&image).await?;
+
+    println!("{:#?}", analysis.receipt);
+    println!("usage: {:?}", analysis.usage);
+    if let Some(cost) = analysis.usage.estimate_cost(&analysis.model_id) {
+        println!("estimated cost: ${:.4}", cost.usd);
+    }
+
+    if post_to_slack {
+        let slack = SlackClient::from_env()?;
+        slack
+            .post_receipt_with_image(&analysis.receipt, Some(&image))
+            .await?;
+    }
 
     Ok(())
 }
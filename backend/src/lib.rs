@@ -1,12 +1,19 @@
 use anyhow::Result;
-use aws_config::Region;
-use aws_sdk_bedrockruntime::primitives::Blob;
-use base64::{engine::general_purpose, Engine as _};
 use image::ImageFormat;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::io::Cursor;
 
+pub mod analyzer;
+pub mod money;
+pub mod slack;
+pub mod usage;
+
+use money::Money;
+
+/// Allowed drift (in minor units) between the summed items and the stated
+/// total before a receipt is flagged as mismatched.
+const TOTAL_TOLERANCE_MINOR_UNITS: i64 = 1;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Deserialize, Serialize)]
 pub struct Receipt {
     pub brand: String,
@@ -14,6 +21,12 @@ pub struct Receipt {
     pub date: String,
     pub items: Vec<ReceiptItem>,
     pub total: String,
+    #[serde(default)]
+    pub total_money: Option<Money>,
+    /// Whether the summed item prices match `total_money` within
+    /// [`TOTAL_TOLERANCE_MINOR_UNITS`]. `None` until money has been parsed.
+    #[serde(default)]
+    pub totals_match: Option<bool>,
     pub confidence: f64,
 }
 
@@ -21,6 +34,70 @@ pub struct Receipt {
 pub struct ReceiptItem {
     pub name: String,
     pub price: String,
+    #[serde(default)]
+    pub price_money: Option<Money>,
+}
+
+impl Receipt {
+    /// Parses [`Money`] out of the raw `total`/`price` strings and stores it
+    /// alongside them, lowering `confidence` if any currency marker wasn't
+    /// recognized or the summed items don't match the stated total.
+    pub fn with_parsed_money(mut self) -> Self {
+        let mut any_unrecognized = false;
+
+        for item in &mut self.items {
+            let parsed = money::parse_money(&item.price);
+            any_unrecognized |= !parsed.currency_recognized;
+            item.price_money = Some(parsed.money);
+        }
+
+        let parsed_total = money::parse_money(&self.total);
+        any_unrecognized |= !parsed_total.currency_recognized;
+        self.total_money = Some(parsed_total.money);
+
+        if any_unrecognized {
+            self.confidence *= 0.5;
+        }
+
+        let totals_match = self.items_total_matches(TOTAL_TOLERANCE_MINOR_UNITS);
+        self.totals_match = Some(totals_match);
+        if !totals_match {
+            self.confidence *= 0.9;
+        }
+
+        self
+    }
+
+    /// Sums `items`' parsed prices, or `None` if money hasn't been parsed yet
+    /// or an item's currency doesn't match the total's.
+    pub fn items_total(&self) -> Option<Money> {
+        let total_money = self.total_money?;
+
+        let mut minor_units = 0i64;
+        for item in &self.items {
+            let price = item.price_money?;
+            if price.currency != total_money.currency {
+                return None;
+            }
+            minor_units += price.minor_units;
+        }
+
+        Some(Money {
+            minor_units,
+            currency: total_money.currency,
+        })
+    }
+
+    /// Whether the summed items match the stated total within
+    /// `tolerance_minor_units`.
+    pub fn items_total_matches(&self, tolerance_minor_units: i64) -> bool {
+        match (self.total_money, self.items_total()) {
+            (Some(total), Some(items_total)) => {
+                (total.minor_units - items_total.minor_units).abs() <= tolerance_minor_units
+            }
+            _ => false,
+        }
+    }
 }
 
 pub async fn resize_image(image_data: &[u8], max_size_bytes: usize) -> Result<Vec<u8>> {
@@ -48,106 +125,3 @@ pub async fn resize_image(image_data: &[u8], max_size_bytes: usize) -> Result<Ve
 
     Err(anyhow::anyhow!("Failed to resize image to target size"))
 }
-
-pub async fn analyze(client: &aws_sdk_bedrockruntime::Client, image: &[u8]) -> Result<Receipt> {
-    let image = resize_image(image, 1024 * 1024).await?;
-    let base64_image = general_purpose::STANDARD.encode(image);
-
-    let example_format = json!({
-        "brand": "{店の名前}",
-        "store": "{支店名}",
-        "date": "{日付 YYYY-MM-DD}",
-        "items": [
-            {
-                "name": "{商品名1}",
-                "price": "{価格1}"
-            },
-            {
-                "name": "{商品名2}",
-                "price": "{価格2}"
-            },
-            {
-                "name": "外税",
-                "price": "{外税の項目があれば、アイテムの総和が税込み合計金額と一致するように調整用の外税の合計金額を入れる。なければ外税の項目自体を削除してよい}"
-            }
-        ],
-        "total": "{税込み合計金額、アイテムから計算せず、レシートに書いてあるとおりを入れる}",
-        "confidence": "{どれくらい正確に読み取れたと判断できるか、0.0 から 1.0 の間の数値を入れる}"
-    });
-
-    let bedrock_request = json!({
-        "anthropic_version": "bedrock-2023-05-31",
-        "max_tokens": 1000,
-        "messages": [
-            {
-                "role": "user",
-                "content": [
-                    {
-                        "type": "image",
-                        "source": {
-                            "type": "base64",
-                            "media_type": "image/jpeg",
-                            "data": base64_image
-                        }
-                    },
-                    {
-                        "type": "text",
-                        "text": format!(
-                            concat!(
-                                "画像のレシートを解析し、店名、日付、購入品目と金額、合計金額を抽出してください。\n",
-                                "\n",
-                                "# 注意\n",
-                                "\n",
-                                "- レシートではなくクレジットカード売上票や請求書の場合もあります。この場合は内訳は書いていないので、「不明」というアイテムが一つのレシートとみなしてください。\n",
-                                "- 円記号を目印にして認識してください。\n",
-                                "- 商品は1つしかない場合もあれば複数個ある場合もあります。\n",
-                                "- レシートに書いてある合計金額を最も優先し、計算が合わない場合は商品の項目で調整してください。\n",
-                                "- 不明な箇所には `不明` と出力してください。\n",
-                                "\n",
-                                "# 出力フォーマット\n",
-                                "\n",
-                                "- 以下の形式の JSON フォーマットを返してください。\n",
-                                "- 説明は一切不要なので結果のみを返してください。\n",
-                                "- Markdown のマーカーブロックでは囲まないでください。\n",
-                                "\n",
-                                "```json\n",
-                                "{example_format}\n",
-                                "```\n",
-                            ),
-                            example_format=example_format
-                        ),
-                    }
-                ]
-            }
-        ]
-    });
-
-    let response = client
-        .invoke_model()
-        .model_id("anthropic.claude-3-5-sonnet-20240620-v1:0")
-        .content_type("application/json")
-        .accept("application/json")
-        .body(Blob::new(serde_json::to_string(&bedrock_request)?))
-        .send()
-        .await?;
-
-    let response_body: serde_json::Value = serde_json::from_slice(response.body().as_ref())?;
-    let analysis_result = response_body["content"][0]["text"]
-        .as_str()
-        .ok_or_else(|| anyhow::anyhow!("Failed to extract analysis result"))?;
-
-    let usage = &response_body["usage"];
-    eprintln!("usage: {usage}");
-
-    serde_json::from_str(analysis_result).map_err(Into::into)
-}
-
-pub async fn create_bedrock_client() -> aws_sdk_bedrockruntime::Client {
-    let config = aws_config::load_defaults(aws_config::BehaviorVersion::v2024_03_28())
-        .await
-        .into_builder()
-        .region(Region::new("us-east-1"))
-        .build();
-
-    aws_sdk_bedrockruntime::Client::new(&config)
-}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::env;
+
+use serde::{Deserialize, Serialize};
+
+/// A currency recognized from a receipt's raw price text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Currency {
+    Jpy,
+    Usd,
+    Eur,
+    /// The raw text didn't contain a currency marker we recognize.
+    Unknown,
+}
+
+impl Currency {
+    /// Number of decimal places of the minor unit (e.g. 2 for USD cents, 0 for JPY).
+    pub fn minor_unit_decimals(&self) -> u32 {
+        match self {
+            Currency::Jpy | Currency::Unknown => 0,
+            Currency::Usd | Currency::Eur => 2,
+        }
+    }
+
+    /// The ISO 4217 code for this currency (`"UNKNOWN"` for `Currency::Unknown`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Jpy => "JPY",
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Unknown => "UNKNOWN",
+        }
+    }
+
+    /// Parses an ISO 4217 code (case-insensitive) into a `Currency`.
+    pub fn parse_code(code: &str) -> Option<Currency> {
+        match code.to_ascii_uppercase().as_str() {
+            "JPY" => Some(Currency::Jpy),
+            "USD" => Some(Currency::Usd),
+            "EUR" => Some(Currency::Eur),
+            _ => None,
+        }
+    }
+}
+
+/// A monetary amount stored as an exact integer number of minor units (e.g.
+/// cents), avoiding the float-rounding issues of a plain `f64` amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub struct Money {
+    pub minor_units: i64,
+    pub currency: Currency,
+}
+
+/// The result of parsing a raw price string: the `Money` value and whether
+/// its currency marker was recognized.
+pub struct ParsedMoney {
+    pub money: Money,
+    pub currency_recognized: bool,
+}
+
+/// Leading minus-sign variants a vision model might emit for a negative or
+/// adjustment amount, including the Unicode (U+2212) and full-width
+/// (U+FF0D) minus signs alongside the ASCII hyphen-minus.
+const MINUS_SIGNS: [char; 3] = ['-', '\u{2212}', '\u{FF0D}'];
+
+/// Whether `raw` starts with a minus sign once currency markers and
+/// whitespace are skipped. Anchoring to the leading sign (rather than
+/// searching the whole string for a hyphen) avoids misreading stray
+/// punctuation elsewhere in the string as a negative amount.
+fn has_leading_minus_sign(raw: &str) -> bool {
+    let first_significant = raw
+        .chars()
+        .find(|c| !c.is_whitespace() && !matches!(c, '¥' | '円' | '$' | '€'));
+    matches!(first_significant, Some(c) if MINUS_SIGNS.contains(&c))
+}
+
+fn detect_currency(raw: &str) -> (Currency, bool) {
+    if raw.contains('¥') || raw.contains('円') {
+        (Currency::Jpy, true)
+    } else if raw.contains('$') {
+        (Currency::Usd, true)
+    } else if raw.contains('€') {
+        (Currency::Eur, true)
+    } else {
+        (Currency::Unknown, false)
+    }
+}
+
+/// Parses a free-form price string like `"¥1,200"` or `"$12.00"` into a
+/// [`Money`] value, stripping currency markers, thousands separators and
+/// whitespace. Unknown currencies fall back to a raw passthrough (treated as
+/// having zero minor units) with `currency_recognized` set to `false`.
+pub fn parse_money(raw: &str) -> ParsedMoney {
+    let (currency, currency_recognized) = detect_currency(raw);
+    let decimals = currency.minor_unit_decimals();
+    let is_negative = has_leading_minus_sign(raw);
+
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+
+    let mut parts = cleaned.splitn(2, '.');
+    let integer_part = parts.next().unwrap_or("");
+    let fractional_part = parts.next().unwrap_or("");
+
+    let integer_value: i64 = integer_part.parse().unwrap_or(0);
+    let scale = 10i64.pow(decimals);
+
+    let fractional_value: i64 = if decimals == 0 || fractional_part.is_empty() {
+        0
+    } else {
+        let padded: String = fractional_part
+            .chars()
+            .chain(std::iter::repeat('0'))
+            .take(decimals as usize)
+            .collect();
+        padded.parse().unwrap_or(0)
+    };
+
+    let magnitude = integer_value * scale + fractional_value;
+
+    ParsedMoney {
+        money: Money {
+            minor_units: if is_negative { -magnitude } else { magnitude },
+            currency,
+        },
+        currency_recognized,
+    }
+}
+
+/// Supplies exchange rates between currencies for [`Money::convert`].
+pub trait ExchangeRateProvider: Send + Sync {
+    /// Units of `to` per one unit of `from`, or `None` if the rate isn't known.
+    fn rate(&self, from: Currency, to: Currency) -> Option<f64>;
+}
+
+/// An in-memory [`ExchangeRateProvider`] backed by a fixed rate table.
+#[derive(Debug, Clone, Default)]
+pub struct StaticRateTable {
+    rates: HashMap<(Currency, Currency), f64>,
+}
+
+impl StaticRateTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rate(mut self, from: Currency, to: Currency, rate: f64) -> Self {
+        self.rates.insert((from, to), rate);
+        self
+    }
+
+    /// Builds a rate table from `FX_RATE_<FROM>_<TO>` environment variables
+    /// (e.g. `FX_RATE_JPY_USD=0.0067`), for every pair of known currencies.
+    pub fn from_env() -> Self {
+        let mut table = Self::new();
+        let currencies = [Currency::Jpy, Currency::Usd, Currency::Eur];
+
+        for &from in &currencies {
+            for &to in &currencies {
+                if from == to {
+                    continue;
+                }
+
+                let var = format!("FX_RATE_{}_{}", from.code(), to.code());
+                if let Some(rate) = env::var(&var).ok().and_then(|v| v.parse::<f64>().ok()) {
+                    table = table.with_rate(from, to, rate);
+                }
+            }
+        }
+
+        table
+    }
+}
+
+impl ExchangeRateProvider for StaticRateTable {
+    fn rate(&self, from: Currency, to: Currency) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.rates.get(&(from, to)).copied()
+    }
+}
+
+impl Money {
+    /// Converts this amount to `target`, using `provider` for the rate.
+    /// Returns `None` if the provider doesn't know the rate.
+    pub fn convert(&self, target: Currency, provider: &dyn ExchangeRateProvider) -> Option<Money> {
+        let rate = provider.rate(self.currency, target)?;
+        let source_scale = 10f64.powi(self.currency.minor_unit_decimals() as i32);
+        let target_scale = 10f64.powi(target.minor_unit_decimals() as i32);
+
+        let minor_units = (self.minor_units as f64 * rate * target_scale / source_scale).round() as i64;
+        Some(Money {
+            minor_units,
+            currency: target,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_jpy_with_thousands_separator() {
+        let parsed = parse_money("¥1,200");
+        assert!(parsed.currency_recognized);
+        assert_eq!(
+            parsed.money,
+            Money {
+                minor_units: 1200,
+                currency: Currency::Jpy,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_usd_with_decimal_point() {
+        let parsed = parse_money("$12.00");
+        assert!(parsed.currency_recognized);
+        assert_eq!(
+            parsed.money,
+            Money {
+                minor_units: 1200,
+                currency: Currency::Usd,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_negative_external_tax_line() {
+        let parsed = parse_money("-300円");
+        assert!(parsed.currency_recognized);
+        assert_eq!(
+            parsed.money,
+            Money {
+                minor_units: -300,
+                currency: Currency::Jpy,
+            }
+        );
+    }
+
+    #[test]
+    fn recognizes_unicode_and_fullwidth_minus_signs() {
+        assert_eq!(parse_money("\u{2212}500円").money.minor_units, -500);
+        assert_eq!(parse_money("\u{FF0D}500円").money.minor_units, -500);
+    }
+
+    #[test]
+    fn unrecognized_currency_falls_back_to_raw_passthrough() {
+        let parsed = parse_money("1200");
+        assert!(!parsed.currency_recognized);
+        assert_eq!(
+            parsed.money,
+            Money {
+                minor_units: 1200,
+                currency: Currency::Unknown,
+            }
+        );
+    }
+
+    #[test]
+    fn hyphen_not_in_leading_position_does_not_flip_sign() {
+        // A stray hyphen elsewhere in the string (e.g. OCR noise) shouldn't
+        // be mistaken for a negative-amount marker.
+        let parsed = parse_money("12-00円");
+        assert_eq!(parsed.money.minor_units, 1200);
+    }
+
+    #[test]
+    fn receipt_totals_match_flags_mismatched_items() {
+        use crate::{Receipt, ReceiptItem};
+
+        let mismatched = Receipt {
+            brand: "Test Mart".to_string(),
+            store: "Shibuya".to_string(),
+            date: "2026-07-30".to_string(),
+            items: vec![ReceiptItem {
+                name: "Coffee".to_string(),
+                price: "¥300".to_string(),
+                price_money: None,
+            }],
+            total: "¥1,000".to_string(),
+            total_money: None,
+            totals_match: None,
+            confidence: 0.9,
+        }
+        .with_parsed_money();
+
+        assert_eq!(mismatched.totals_match, Some(false));
+        assert!(mismatched.confidence < 0.9);
+
+        let matching = Receipt {
+            items: vec![ReceiptItem {
+                name: "Coffee".to_string(),
+                price: "¥1,000".to_string(),
+                price_money: None,
+            }],
+            ..mismatched.clone()
+        }
+        .with_parsed_money();
+
+        assert_eq!(matching.totals_match, Some(true));
+    }
+}
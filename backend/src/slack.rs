@@ -0,0 +1,277 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::env;
+
+use crate::Receipt;
+
+/// Posts analyzed receipts to a Slack channel via an incoming webhook.
+pub struct SlackClient {
+    http: reqwest::Client,
+    webhook_url: String,
+    bot_token: Option<String>,
+}
+
+impl SlackClient {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            webhook_url,
+            bot_token: None,
+        }
+    }
+
+    /// Enables image attachments by uploading via the Slack Web API, which
+    /// (unlike an incoming webhook) needs a bot token.
+    pub fn with_bot_token(mut self, bot_token: String) -> Self {
+        self.bot_token = Some(bot_token);
+        self
+    }
+
+    /// Reads `SLACK_WEBHOOK_URL` (required) and `SLACK_BOT_TOKEN` (optional,
+    /// only needed to attach the original image) from the environment.
+    pub fn from_env() -> Result<Self> {
+        let webhook_url = env::var("SLACK_WEBHOOK_URL").context("SLACK_WEBHOOK_URL is not set")?;
+        let mut client = Self::new(webhook_url);
+        if let Ok(bot_token) = env::var("SLACK_BOT_TOKEN") {
+            client = client.with_bot_token(bot_token);
+        }
+        Ok(client)
+    }
+
+    pub async fn post_receipt(&self, receipt: &Receipt) -> Result<()> {
+        self.post_receipt_with_image(receipt, None).await
+    }
+
+    /// Posts `receipt` as a Block Kit message, optionally attaching the
+    /// original image (requires a bot token). Without a token, or if the
+    /// upload fails, the image is skipped with a warning on stderr and the
+    /// message is still posted.
+    pub async fn post_receipt_with_image(
+        &self,
+        receipt: &Receipt,
+        image: Option<&[u8]>,
+    ) -> Result<()> {
+        let mut blocks = build_blocks(receipt);
+
+        if let Some(image) = image {
+            match self.upload_image(image).await {
+                Ok(Some(file_id)) => blocks.push(json!({
+                    "type": "image",
+                    "slack_file": { "id": file_id },
+                    "alt_text": "receipt image",
+                })),
+                Ok(None) => eprintln!("SLACK_BOT_TOKEN is not set; skipping image attachment"),
+                Err(err) => eprintln!("failed to attach receipt image to Slack message: {err}"),
+            }
+        }
+
+        let response = self
+            .http
+            .post(&self.webhook_url)
+            .json(&json!({ "blocks": blocks }))
+            .send()
+            .await
+            .context("failed to reach Slack webhook")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("Slack webhook rejected the message ({status}): {body}");
+        }
+
+        Ok(())
+    }
+
+    /// Uploads `image` via the external-upload flow (`files.getUploadURLExternal`
+    /// + POST of the bytes + `files.completeUploadExternal`) and returns its Slack file id,
+    /// suitable for a Block Kit `image` block's `slack_file` reference.
+    /// Returns `None` only when no bot token is configured.
+    async fn upload_image(&self, image: &[u8]) -> Result<Option<String>> {
+        let Some(bot_token) = &self.bot_token else {
+            return Ok(None);
+        };
+
+        let filename = "receipt.jpg";
+
+        let upload_url_response: Value = self
+            .http
+            .post("https://slack.com/api/files.getUploadURLExternal")
+            .bearer_auth(bot_token)
+            .form(&[("filename", filename), ("length", &image.len().to_string())])
+            .send()
+            .await
+            .context("failed to request a Slack upload URL")?
+            .json()
+            .await
+            .context("failed to parse files.getUploadURLExternal response")?;
+
+        if upload_url_response["ok"].as_bool() != Some(true) {
+            bail!("files.getUploadURLExternal failed: {upload_url_response}");
+        }
+
+        let upload_url = upload_url_response["upload_url"]
+            .as_str()
+            .context("files.getUploadURLExternal response missing upload_url")?;
+        let file_id = upload_url_response["file_id"]
+            .as_str()
+            .context("files.getUploadURLExternal response missing file_id")?
+            .to_string();
+
+        let upload_response = self
+            .http
+            .post(upload_url)
+            .body(image.to_vec())
+            .send()
+            .await
+            .context("failed to upload image bytes to Slack")?;
+
+        if !upload_response.status().is_success() {
+            bail!(
+                "Slack rejected the uploaded file ({})",
+                upload_response.status()
+            );
+        }
+
+        let complete_response: Value = self
+            .http
+            .post("https://slack.com/api/files.completeUploadExternal")
+            .bearer_auth(bot_token)
+            .json(&json!({
+                "files": [{ "id": file_id, "title": filename }],
+            }))
+            .send()
+            .await
+            .context("failed to complete Slack file upload")?
+            .json()
+            .await
+            .context("failed to parse files.completeUploadExternal response")?;
+
+        if complete_response["ok"].as_bool() != Some(true) {
+            bail!("files.completeUploadExternal failed: {complete_response}");
+        }
+
+        Ok(Some(file_id))
+    }
+}
+
+fn build_blocks(receipt: &Receipt) -> Vec<Value> {
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": {
+                "type": "plain_text",
+                "text": format!("{} - {}", receipt.brand, receipt.store),
+                "emoji": true,
+            },
+        }),
+        json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!("*日付:* {}", receipt.date) },
+        }),
+    ];
+
+    if !receipt.items.is_empty() {
+        let items_text = receipt
+            .items
+            .iter()
+            .map(|item| format!("• {} — {}", item.name, item.price))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": items_text },
+        }));
+    }
+
+    blocks.push(json!({
+        "type": "section",
+        "fields": [
+            { "type": "mrkdwn", "text": format!("*合計:*\n{}", receipt.total) },
+        ],
+    }));
+
+    blocks.push(json!({
+        "type": "context",
+        "elements": [
+            { "type": "mrkdwn", "text": format!("confidence: {:.2}", receipt.confidence) },
+        ],
+    }));
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReceiptItem;
+
+    fn sample_receipt() -> Receipt {
+        Receipt {
+            brand: "Test Mart".to_string(),
+            store: "Shibuya".to_string(),
+            date: "2026-07-30".to_string(),
+            items: vec![ReceiptItem {
+                name: "Coffee".to_string(),
+                price: "¥300".to_string(),
+                price_money: None,
+            }],
+            total: "¥300".to_string(),
+            total_money: None,
+            totals_match: None,
+            confidence: 0.95,
+        }
+    }
+
+    #[test]
+    fn header_includes_brand_and_store() {
+        let blocks = build_blocks(&sample_receipt());
+        let header = &blocks[0];
+        assert_eq!(header["type"], "header");
+        assert_eq!(header["text"]["text"], "Test Mart - Shibuya");
+    }
+
+    #[test]
+    fn items_section_lists_name_and_price() {
+        let blocks = build_blocks(&sample_receipt());
+        let items_section = blocks
+            .iter()
+            .find(|block| {
+                block["type"] == "section"
+                    && block["text"]["text"]
+                        .as_str()
+                        .is_some_and(|text| text.contains("Coffee"))
+            })
+            .expect("items section should be present");
+        assert!(items_section["text"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("¥300"));
+    }
+
+    #[test]
+    fn total_field_is_present() {
+        let blocks = build_blocks(&sample_receipt());
+        let total_section = blocks
+            .iter()
+            .find(|block| block["fields"].is_array())
+            .expect("total section should be present");
+        assert!(total_section["fields"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("¥300"));
+    }
+
+    #[test]
+    fn confidence_context_is_present() {
+        let blocks = build_blocks(&sample_receipt());
+        let context = blocks
+            .last()
+            .expect("at least one block should be present");
+        assert_eq!(context["type"], "context");
+        assert!(context["elements"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("0.95"));
+    }
+}
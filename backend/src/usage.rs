@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::analyzer::{BEDROCK_MODEL_ID, OPENAI_DEFAULT_MODEL};
+
+/// Token counts reported by a model backend for a single analysis call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// An estimated USD cost for a [`Usage`], computed from a per-model pricing
+/// table.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CostEstimate {
+    pub usd: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ModelPricing {
+    input_usd_per_1k: f64,
+    output_usd_per_1k: f64,
+}
+
+/// USD per 1K tokens, keyed by the same model id constants the analyzer
+/// backends use. Unknown models have no estimate.
+fn pricing_for_model(model_id: &str) -> Option<ModelPricing> {
+    match model_id {
+        BEDROCK_MODEL_ID => Some(ModelPricing {
+            input_usd_per_1k: 0.003,
+            output_usd_per_1k: 0.015,
+        }),
+        OPENAI_DEFAULT_MODEL => Some(ModelPricing {
+            input_usd_per_1k: 0.0025,
+            output_usd_per_1k: 0.01,
+        }),
+        _ => None,
+    }
+}
+
+impl Usage {
+    /// Estimates the USD cost of this usage for the given model id, or
+    /// `None` if the model isn't in the pricing table. Logs when a model has
+    /// no pricing entry, since a silent `None` would make `/usage` quietly
+    /// stop accounting for that traffic (e.g. a custom `OPENAI_MODEL`).
+    pub fn estimate_cost(&self, model_id: &str) -> Option<CostEstimate> {
+        let Some(pricing) = pricing_for_model(model_id) else {
+            eprintln!("no pricing entry for model `{model_id}`; cost tracking is blind for this request");
+            return None;
+        };
+
+        let usd = (self.input_tokens as f64 / 1000.0) * pricing.input_usd_per_1k
+            + (self.output_tokens as f64 / 1000.0) * pricing.output_usd_per_1k;
+        Some(CostEstimate { usd })
+    }
+}
+
+/// Running totals accumulated by a [`UsageAggregator`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageTotals {
+    pub request_count: u64,
+    pub total_input_tokens: u64,
+    pub total_output_tokens: u64,
+    pub total_cost_usd: f64,
+}
+
+/// Accumulates token usage and estimated spend across requests so operators
+/// can monitor Bedrock/OpenAI cost without opening a provider console.
+#[derive(Debug, Default)]
+pub struct UsageAggregator {
+    totals: Mutex<UsageTotals>,
+}
+
+impl UsageAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, usage: Usage, cost: Option<CostEstimate>) {
+        let mut totals = self.totals.lock().unwrap();
+        totals.request_count += 1;
+        totals.total_input_tokens += usage.input_tokens;
+        totals.total_output_tokens += usage.output_tokens;
+        totals.total_cost_usd += cost.map_or(0.0, |c| c.usd);
+    }
+
+    pub fn snapshot(&self) -> UsageTotals {
+        self.totals.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_known_bedrock_model() {
+        let usage = Usage {
+            input_tokens: 1000,
+            output_tokens: 1000,
+        };
+        let estimate = usage.estimate_cost(BEDROCK_MODEL_ID).expect("bedrock model is priced");
+        assert_eq!(estimate.usd, 0.003 + 0.015);
+    }
+
+    #[test]
+    fn prices_known_openai_model() {
+        let usage = Usage {
+            input_tokens: 1000,
+            output_tokens: 1000,
+        };
+        let estimate = usage
+            .estimate_cost(OPENAI_DEFAULT_MODEL)
+            .expect("openai default model is priced");
+        assert_eq!(estimate.usd, 0.0025 + 0.01);
+    }
+
+    #[test]
+    fn unknown_model_has_no_estimate() {
+        let usage = Usage {
+            input_tokens: 1000,
+            output_tokens: 1000,
+        };
+        assert!(usage.estimate_cost("some-custom-model").is_none());
+    }
+}